@@ -0,0 +1,117 @@
+use std::{collections::BTreeSet, error::Error, fmt};
+
+use url::Url;
+
+/// How a [`Compiler`](crate::Compiler) resolves external references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompileMode {
+    /// Call the registered `UrlLoader` synchronously for each missing
+    /// document (the default).
+    #[default]
+    Loader,
+    /// Never call a loader. Instead, compilation fails with
+    /// [`UnresolvedReferences`] listing every external document it could not
+    /// satisfy from the already-added resources, so the caller can fetch them
+    /// by any means (async, batched, cached) and retry.
+    Deferred,
+}
+
+/// A single external reference that compilation could not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedRef {
+    /// The absolute URL of the document that must be fetched.
+    pub url: Url,
+    /// JSON pointer to the `$ref` (or `$schema`/`$recursiveRef`/...) that
+    /// introduced the reference.
+    pub ref_location: String,
+}
+
+/// The set of external documents a deferred compilation needs before it can
+/// finish. Returned by `Compiler::compile` when running in
+/// [`CompileMode::Deferred`].
+///
+/// The caller fetches each [`url`](UnresolvedRef::url), feeds the document back
+/// via `Compiler::add_resource`, and retries `compile`, looping until the set
+/// is empty:
+///
+/// ```ignore
+/// loop {
+///     match compiler.compile(&mut schemas, root.clone()) {
+///         Ok(idx) => break idx,
+///         Err(CompileError::Unresolved(unresolved)) => {
+///             for url in unresolved.urls() {
+///                 compiler.add_resource(url.as_str(), fetch(url).await?)?;
+///             }
+///         }
+///         Err(e) => return Err(e),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnresolvedReferences(Vec<UnresolvedRef>);
+
+impl UnresolvedReferences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a reference that could not be resolved.
+    pub fn insert(&mut self, url: Url, ref_location: String) {
+        self.0.push(UnresolvedRef { url, ref_location });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The distinct documents to fetch, each needed at least once.
+    pub fn urls(&self) -> impl Iterator<Item = &Url> {
+        self.0
+            .iter()
+            .map(|r| &r.url)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    }
+
+    /// Every unresolved reference, including the originating location.
+    pub fn refs(&self) -> &[UnresolvedRef] {
+        &self.0
+    }
+}
+
+impl fmt::Display for UnresolvedReferences {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unresolved references:")?;
+        for r in &self.0 {
+            write!(f, " {} (at {})", r.url, r.ref_location)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnresolvedReferences {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_urls() {
+        let mut unresolved = UnresolvedReferences::new();
+        let a = Url::parse("http://a.com/x.json").unwrap();
+        let b = Url::parse("http://b.com/y.json").unwrap();
+        unresolved.insert(a.clone(), "/properties/foo/$ref".into());
+        unresolved.insert(a.clone(), "/properties/bar/$ref".into());
+        unresolved.insert(b.clone(), "/$ref".into());
+
+        assert!(!unresolved.is_empty());
+        assert_eq!(unresolved.refs().len(), 3); // both locations retained
+        let urls: Vec<_> = unresolved.urls().cloned().collect();
+        assert_eq!(urls, vec![a, b]); // fetched once each
+    }
+
+    #[test]
+    fn test_default_mode_is_loader() {
+        assert_eq!(CompileMode::default(), CompileMode::Loader);
+    }
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, collections::HashMap, str::Utf8Error};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    str::Utf8Error,
+};
 
 use once_cell::sync::Lazy;
 use serde_json::Value;
@@ -6,9 +10,108 @@ use url::Url;
 
 use crate::{root::Resource, util::*};
 
-const POS_SELF: u8 = 1 << 0;
-const POS_PROP: u8 = 1 << 1;
-const POS_ITEM: u8 = 1 << 2;
+/// A single step taken from a keyword's value to reach a nested subschema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// The value is itself a schema.
+    Self_,
+    /// The value is an object whose property values are schemas.
+    Prop,
+    /// The value is an array whose items are schemas.
+    Item,
+}
+
+/// A standard vocabulary that a 2019-09/2020-12 (meta-)schema can enable or
+/// disable through its `$vocabulary` object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Vocabulary {
+    Core,
+    Applicator,
+    Validation,
+    Unevaluated,
+    FormatAnnotation,
+    FormatAssertion,
+    Content,
+    MetaData,
+}
+
+impl Vocabulary {
+    /// Recognizes a vocabulary from its `$vocabulary` URI, for both the
+    /// 2019-09 and 2020-12 dialects. Unknown URIs return `None`.
+    fn from_url(url: &str) -> Option<Vocabulary> {
+        let suffix = url
+            .strip_prefix("https://json-schema.org/")
+            .or_else(|| url.strip_prefix("http://json-schema.org/"))?;
+        let name = suffix
+            .strip_prefix("draft/2020-12/vocab/")
+            .or_else(|| suffix.strip_prefix("draft/2019-09/vocab/"))?;
+        Some(match name {
+            "core" => Vocabulary::Core,
+            "applicator" => Vocabulary::Applicator,
+            "validation" => Vocabulary::Validation,
+            "unevaluated" => Vocabulary::Unevaluated,
+            // 2019-09 has a single "format" vocabulary (annotation semantics);
+            // 2020-12 splits it into annotation and assertion vocabularies.
+            "format-annotation" | "format" => Vocabulary::FormatAnnotation,
+            "format-assertion" => Vocabulary::FormatAssertion,
+            "content" => Vocabulary::Content,
+            "meta-data" => Vocabulary::MetaData,
+            _ => return None,
+        })
+    }
+}
+
+// The non-subschema keywords each vocabulary contributes (its
+// validation/assertion/annotation surface). The applicator and unevaluated
+// vocabularies contribute only subschema-bearing keywords, tracked separately.
+fn vocabulary_keywords(vocab: Vocabulary) -> &'static [&'static str] {
+    match vocab {
+        Vocabulary::Core => &[
+            "$id",
+            "$ref",
+            "$schema",
+            "$anchor",
+            "$dynamicRef",
+            "$dynamicAnchor",
+            "$vocabulary",
+            "$comment",
+        ],
+        Vocabulary::Applicator | Vocabulary::Unevaluated => &[],
+        Vocabulary::Validation => &[
+            "type",
+            "enum",
+            "const",
+            "multipleOf",
+            "maximum",
+            "exclusiveMaximum",
+            "minimum",
+            "exclusiveMinimum",
+            "maxLength",
+            "minLength",
+            "pattern",
+            "maxItems",
+            "minItems",
+            "uniqueItems",
+            "maxContains",
+            "minContains",
+            "maxProperties",
+            "minProperties",
+            "required",
+            "dependentRequired",
+        ],
+        Vocabulary::FormatAnnotation | Vocabulary::FormatAssertion => &["format"],
+        Vocabulary::Content => &["contentEncoding", "contentMediaType", "contentSchema"],
+        Vocabulary::MetaData => &[
+            "title",
+            "description",
+            "default",
+            "deprecated",
+            "readOnly",
+            "writeOnly",
+            "examples",
+        ],
+    }
+}
 
 static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
     version: 4,
@@ -16,25 +119,28 @@ static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
     bool_schema: false,
     subschemas: HashMap::from([
         // core
-        ("definitions", POS_PROP),
-        ("not", POS_SELF),
-        ("allOf", POS_ITEM),
-        ("anyOf", POS_ITEM),
-        ("oneOf", POS_ITEM),
+        ("definitions", vec![vec![Step::Prop]]),
+        ("not", vec![vec![Step::Self_]]),
+        ("allOf", vec![vec![Step::Item]]),
+        ("anyOf", vec![vec![Step::Item]]),
+        ("oneOf", vec![vec![Step::Item]]),
         // object
-        ("properties", POS_PROP),
-        ("additionalProperties", POS_SELF),
-        ("patternProperties", POS_PROP),
+        ("properties", vec![vec![Step::Prop]]),
+        ("additionalProperties", vec![vec![Step::Self_]]),
+        ("patternProperties", vec![vec![Step::Prop]]),
         // array
-        ("items", POS_SELF | POS_ITEM),
-        ("additionalItems", POS_SELF),
-        ("dependencies", POS_PROP),
+        ("items", vec![vec![Step::Self_], vec![Step::Item]]),
+        ("additionalItems", vec![vec![Step::Self_]]),
+        ("dependencies", vec![vec![Step::Prop]]),
     ]),
 });
 
 static DRAFT6: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT4.subschemas.clone();
-    subschemas.extend([("propertyNames", POS_SELF), ("contains", POS_SELF)]);
+    subschemas.extend([
+        ("propertyNames", vec![vec![Step::Self_]]),
+        ("contains", vec![vec![Step::Self_]]),
+    ]);
     Draft {
         version: 6,
         id: "$id",
@@ -45,7 +151,11 @@ static DRAFT6: Lazy<Draft> = Lazy::new(|| {
 
 static DRAFT7: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT6.subschemas.clone();
-    subschemas.extend([("if", POS_SELF), ("then", POS_SELF), ("else", POS_SELF)]);
+    subschemas.extend([
+        ("if", vec![vec![Step::Self_]]),
+        ("then", vec![vec![Step::Self_]]),
+        ("else", vec![vec![Step::Self_]]),
+    ]);
     Draft {
         version: 7,
         id: "$id",
@@ -57,10 +167,10 @@ static DRAFT7: Lazy<Draft> = Lazy::new(|| {
 static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT7.subschemas.clone();
     subschemas.extend([
-        ("$defs", POS_PROP),
-        ("dependentSchemas", POS_PROP),
-        ("unevaluatedProperties", POS_SELF),
-        ("unevaluatedItems", POS_SELF),
+        ("$defs", vec![vec![Step::Prop]]),
+        ("dependentSchemas", vec![vec![Step::Prop]]),
+        ("unevaluatedProperties", vec![vec![Step::Self_]]),
+        ("unevaluatedItems", vec![vec![Step::Self_]]),
     ]);
     Draft {
         version: 2019,
@@ -72,7 +182,11 @@ static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
 
 static DRAFT2020: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT2019.subschemas.clone();
-    subschemas.extend([("prefixItems", POS_ITEM)]);
+    subschemas.extend([
+        ("prefixItems", vec![vec![Step::Item]]),
+        // object of property-names -> object of string-values -> schema
+        ("propertyDependencies", vec![vec![Step::Prop, Step::Prop]]),
+    ]);
     Draft {
         version: 2020,
         id: "$id",
@@ -91,7 +205,7 @@ pub(crate) struct Draft {
     version: usize,
     id: &'static str,
     bool_schema: bool,
-    subschemas: HashMap<&'static str, u8>,
+    subschemas: HashMap<&'static str, Vec<Vec<Step>>>,
 }
 
 impl Draft {
@@ -120,6 +234,71 @@ impl Draft {
         }
     }
 
+    // Parses a custom meta-schema's `$vocabulary` object into the set of
+    // enabled vocabularies. An unknown vocabulary that is *required* (value
+    // `true`) is an error; an unknown optional one is ignored. `core` is
+    // always enabled, per spec, whether or not it is listed.
+    pub(crate) fn resolve_vocabularies(
+        &self,
+        vocabulary: &serde_json::Map<String, Value>,
+    ) -> Result<HashSet<Vocabulary>, String> {
+        let mut enabled = HashSet::from([Vocabulary::Core]);
+        for (url, required) in vocabulary {
+            match Vocabulary::from_url(url) {
+                Some(vocab) => {
+                    enabled.insert(vocab);
+                }
+                None if required == &Value::Bool(true) => {
+                    return Err(format!("unknown required vocabulary {url}"));
+                }
+                None => {}
+            }
+        }
+        Ok(enabled)
+    }
+
+    // Builds the effective subschema-location map for the given enabled
+    // vocabularies by keeping only the keywords owned by an enabled vocabulary.
+    pub(crate) fn subschemas_for(
+        &self,
+        enabled: &HashSet<Vocabulary>,
+    ) -> HashMap<&'static str, Vec<Vec<Step>>> {
+        self.subschemas
+            .iter()
+            .filter(|(kw, _)| enabled.contains(&self.keyword_vocabulary(kw)))
+            .map(|(&kw, paths)| (kw, paths.clone()))
+            .collect()
+    }
+
+    // Builds the effective keyword set for the given enabled vocabularies by
+    // unioning each vocabulary's subschema-bearing keywords with its
+    // validation/assertion/annotation keywords.
+    pub(crate) fn effective_keywords(
+        &self,
+        enabled: &HashSet<Vocabulary>,
+    ) -> HashSet<&'static str> {
+        let mut keywords: HashSet<&'static str> =
+            self.subschemas_for(enabled).into_keys().collect();
+        for &vocab in enabled {
+            keywords.extend(vocabulary_keywords(vocab).iter().copied());
+        }
+        keywords
+    }
+
+    // which vocabulary owns a subschema-bearing keyword. ownership is
+    // draft-specific: the `unevaluated*` keywords gained a dedicated
+    // vocabulary in 2020-12 but live in the applicator vocabulary in 2019-09.
+    // keywords not matched here belong to the applicator vocabulary.
+    fn keyword_vocabulary(&self, kw: &str) -> Vocabulary {
+        match kw {
+            "$defs" | "definitions" => Vocabulary::Core,
+            "unevaluatedProperties" | "unevaluatedItems" if self.version >= 2020 => {
+                Vocabulary::Unevaluated
+            }
+            _ => Vocabulary::Applicator,
+        }
+    }
+
     fn has_anchor(&self, json: &Value, anchor: &str) -> Result<bool, Utf8Error> {
         let Value::Object(obj) = json else {
             return Ok(false);
@@ -177,27 +356,46 @@ impl Draft {
             resources.insert(ptr.clone(), Resource::new(base.as_ref().clone()));
         }
 
-        for (&kw, &pos) in &self.subschemas {
+        for (&kw, paths) in &self.subschemas {
             let Some(v) = obj.get(kw) else {
                 continue;
             };
-            if pos & POS_SELF != 0 {
-                let ptr = format!("{ptr}/{kw}");
-                self.collect_resources(v, base.as_ref(), ptr, resources)?;
+            let kw_ptr = format!("{ptr}/{kw}");
+            for path in paths {
+                self.collect_path(path, v, base.as_ref(), kw_ptr.clone(), resources)?;
             }
-            if pos & POS_ITEM != 0 {
-                if let Value::Array(arr) = v {
-                    for (i, item) in arr.iter().enumerate() {
-                        let ptr = format!("{ptr}/{kw}/{i}");
-                        self.collect_resources(item, base.as_ref(), ptr, resources)?;
+        }
+        Ok(())
+    }
+
+    // walks the remaining `path` from `json` (reached at `ptr`), collecting
+    // resources from every subschema it leads to.
+    fn collect_path(
+        &self,
+        path: &[Step],
+        json: &Value,
+        base: &Url,
+        ptr: String,
+        resources: &mut HashMap<String, Resource>,
+    ) -> Result<(), String> {
+        let Some((step, rest)) = path.split_first() else {
+            return self.collect_resources(json, base, ptr, resources);
+        };
+        match step {
+            Step::Self_ => self.collect_path(rest, json, base, ptr, resources)?,
+            Step::Prop => {
+                if let Value::Object(obj) = json {
+                    for (pname, pvalue) in obj {
+                        let ptr = format!("{ptr}/{}", escape(pname));
+                        self.collect_path(rest, pvalue, base, ptr, resources)?;
                     }
                 }
             }
-            if pos & POS_PROP != 0 {
-                if let Value::Object(obj) = v {
-                    for (pname, pvalue) in obj {
-                        let ptr = format!("{ptr}/{kw}/{}", escape(pname));
-                        self.collect_resources(pvalue, base.as_ref(), ptr, resources)?;
+            Step::Item => {
+                if let Value::Array(arr) = json {
+                    for (i, item) in arr.iter().enumerate() {
+                        let ptr = format!("{ptr}/{i}");
+                        self.collect_path(rest, item, base, ptr, resources)?;
                     }
                 }
             }
@@ -224,6 +422,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_vocabularies() {
+        // unknown required vocabulary is an error
+        let required: serde_json::Map<String, Value> = serde_json::from_str(
+            r#"{ "https://example.com/vocab/custom": true }"#,
+        )
+        .unwrap();
+        assert!(DRAFT2020.resolve_vocabularies(&required).is_err());
+
+        // unknown optional ignored; core always enabled; unevaluated dropped
+        let vocab: serde_json::Map<String, Value> = serde_json::from_str(
+            r#"{
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                "https://example.com/vocab/custom": false
+            }"#,
+        )
+        .unwrap();
+        let enabled = DRAFT2020.resolve_vocabularies(&vocab).unwrap();
+        assert!(enabled.contains(&Vocabulary::Core));
+        assert!(enabled.contains(&Vocabulary::Applicator));
+        assert!(!enabled.contains(&Vocabulary::Unevaluated));
+
+        let subschemas = DRAFT2020.subschemas_for(&enabled);
+        assert!(subschemas.contains_key("properties")); // applicator
+        assert!(subschemas.contains_key("$defs")); // core
+        assert!(!subschemas.contains_key("unevaluatedProperties")); // disabled
+
+        // effective set unions validation keywords from the enabled
+        // vocabularies; validation was not enabled above, so `type` is absent.
+        let keywords = DRAFT2020.effective_keywords(&enabled);
+        assert!(keywords.contains("properties")); // applicator subschema
+        assert!(keywords.contains("$ref")); // core
+        assert!(!keywords.contains("type")); // validation vocab disabled
+
+        let with_validation = {
+            let mut e = enabled.clone();
+            e.insert(Vocabulary::Validation);
+            e
+        };
+        let keywords = DRAFT2020.effective_keywords(&with_validation);
+        assert!(keywords.contains("type")); // now enabled
+        assert!(keywords.contains("required"));
+    }
+
     #[test]
     fn test_lookup_id() {
         let base = Url::parse("http://a.com/schema.json").unwrap();
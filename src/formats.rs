@@ -0,0 +1,373 @@
+use std::{collections::HashMap, net::Ipv6Addr};
+
+use serde_json::Value;
+use url::Url;
+
+/// A `format` assertion. Implementations inspect the instance `value` and
+/// return whether it satisfies the format. Per spec, a format applies only to
+/// strings; every implementor here returns `true` for non-string instances so
+/// that the assertion is a no-op on other types.
+pub trait Format {
+    fn validate(&self, value: &Value) -> bool;
+}
+
+// A bare `fn(&Value) -> bool` (or any closure) is a `Format`, so custom
+// formats can be registered without declaring a type.
+impl<F: Fn(&Value) -> bool> Format for F {
+    fn validate(&self, value: &Value) -> bool {
+        self(value)
+    }
+}
+
+/// The set of formats known to a compiler, keyed by name. Built-ins can be
+/// overridden and custom formats added via [`Formats::register`].
+pub struct Formats(HashMap<String, Box<dyn Format + Send + Sync>>);
+
+impl Formats {
+    /// The built-in validators: `date-time`, `date`, `time`, `duration`,
+    /// `email`, `hostname`, `ipv4`, `ipv6`, `uri`, `uri-reference`, `uuid`,
+    /// `regex` and `json-pointer`.
+    pub fn new() -> Self {
+        let mut formats = Formats(HashMap::new());
+        formats.register("date-time", as_str(validate_date_time));
+        formats.register("date", as_str(validate_date));
+        formats.register("time", as_str(validate_time));
+        formats.register("duration", as_str(validate_duration));
+        formats.register("email", as_str(validate_email));
+        formats.register("hostname", as_str(validate_hostname));
+        formats.register("ipv4", as_str(validate_ipv4));
+        formats.register("ipv6", as_str(validate_ipv6));
+        formats.register("uri", as_str(validate_uri));
+        formats.register("uri-reference", as_str(validate_uri_reference));
+        formats.register("uuid", as_str(validate_uuid));
+        formats.register("regex", as_str(validate_regex));
+        formats.register("json-pointer", as_str(validate_json_pointer));
+        formats
+    }
+
+    /// Registers (or overrides) the format named `name`.
+    pub fn register(&mut self, name: &str, format: Box<dyn Format + Send + Sync>) {
+        self.0.insert(name.to_owned(), format);
+    }
+
+    /// Asserts `value` against the format named `name`. An unknown format is
+    /// treated as an unconstrained annotation, so it trivially passes.
+    pub fn validate(&self, name: &str, value: &Value) -> bool {
+        match self.0.get(name) {
+            Some(format) => format.validate(value),
+            None => true,
+        }
+    }
+}
+
+impl Default for Formats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Lifts a string predicate into a `Format` that passes on non-string values.
+fn as_str(f: fn(&str) -> bool) -> Box<dyn Format + Send + Sync> {
+    Box::new(move |value: &Value| match value {
+        Value::String(s) => f(s),
+        _ => true,
+    })
+}
+
+// -- built-in validators --
+
+fn validate_date(s: &str) -> bool {
+    parse_date(s).is_some()
+}
+
+fn validate_time(s: &str) -> bool {
+    parse_time(s).is_some()
+}
+
+fn validate_date_time(s: &str) -> bool {
+    let Some((date, time)) = s.split_once(['T', 't']) else {
+        return false;
+    };
+    parse_date(date).is_some() && parse_time(time).is_some()
+}
+
+// full-date = YYYY-MM-DD with a calendar-valid day.
+fn parse_date(s: &str) -> Option<(u16, u8, u8)> {
+    let b = s.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return None;
+    }
+    if !b.iter().enumerate().all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit()) {
+        return None;
+    }
+    let year: u16 = s[0..4].parse().ok()?;
+    let month: u8 = s[5..7].parse().ok()?;
+    let day: u8 = s[8..10].parse().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+// full-time = partial-time time-offset, with an optional fractional part and a
+// mandatory offset (`Z` or `±HH:MM`).
+fn parse_time(s: &str) -> Option<()> {
+    let (time, offset) = split_time_offset(s)?;
+
+    let mut parts = time.splitn(3, ':');
+    let hour: u8 = parts.next()?.parse().ok()?;
+    let minute: u8 = parts.next()?.parse().ok()?;
+    let second_part = parts.next()?;
+    let (second_str, frac) = match second_part.split_once('.') {
+        Some((sec, frac)) => (sec, Some(frac)),
+        None => (second_part, None),
+    };
+    let second: u8 = second_str.parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        // second == 60 allows a leap second
+        return None;
+    }
+    if let Some(frac) = frac {
+        if frac.is_empty() || !frac.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+    }
+
+    // offset
+    if offset == "Z" || offset == "z" {
+        return Some(());
+    }
+    let b = offset.as_bytes();
+    if b.len() != 6 || (b[0] != b'+' && b[0] != b'-') || b[3] != b':' {
+        return None;
+    }
+    let oh: u8 = offset[1..3].parse().ok()?;
+    let om: u8 = offset[4..6].parse().ok()?;
+    (oh <= 23 && om <= 59).then_some(())
+}
+
+fn split_time_offset(s: &str) -> Option<(&str, &str)> {
+    if let Some(i) = s.find(['Z', 'z']) {
+        return Some((&s[..i], &s[i..]));
+    }
+    // the offset sign is the last '+'/'-' in the string
+    let i = s.rfind(['+', '-'])?;
+    Some((&s[..i], &s[i..]))
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+// ISO 8601 duration, e.g. `P3Y6M4DT12H30M5S`, `P1W`.
+fn validate_duration(s: &str) -> bool {
+    let Some(rest) = s.strip_prefix('P') else {
+        return false;
+    };
+    if rest.is_empty() {
+        return false;
+    }
+    // week form is exclusive of everything else
+    if let Some(week) = rest.strip_suffix('W') {
+        return !week.is_empty() && week.bytes().all(|c| c.is_ascii_digit());
+    }
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+    if !date.is_empty() && !valid_duration_part(date, "YMD") {
+        return false;
+    }
+    match time {
+        Some(time) => !time.is_empty() && valid_duration_part(time, "HMS"),
+        // at least one component must be present
+        None => !date.is_empty(),
+    }
+}
+
+// checks a run of `<digits><unit>` groups whose units appear in `units` order.
+fn valid_duration_part(s: &str, units: &str) -> bool {
+    let mut units = units.bytes().peekable();
+    let mut digits = String::new();
+    for c in s.bytes() {
+        if c.is_ascii_digit() {
+            digits.push(c as char);
+            continue;
+        }
+        if digits.is_empty() {
+            return false;
+        }
+        loop {
+            match units.next() {
+                Some(u) if u == c => break,
+                Some(_) => continue,
+                None => return false,
+            }
+        }
+        digits.clear();
+    }
+    digits.is_empty()
+}
+
+fn validate_email(s: &str) -> bool {
+    // split on the first '@'; the local part must not contain one
+    let Some((local, domain)) = s.split_once('@') else {
+        return false;
+    };
+    !local.is_empty() && local.len() <= 64 && validate_hostname(domain)
+}
+
+fn validate_hostname(s: &str) -> bool {
+    let s = s.strip_suffix('.').unwrap_or(s);
+    if s.is_empty() || s.len() > 253 {
+        return false;
+    }
+    s.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-')
+    })
+}
+
+fn validate_ipv4(s: &str) -> bool {
+    let mut octets = 0;
+    for part in s.split('.') {
+        octets += 1;
+        // reject leading zeros and non-octet values
+        if part.len() > 1 && part.starts_with('0') {
+            return false;
+        }
+        if !part.bytes().all(|c| c.is_ascii_digit()) || part.parse::<u8>().is_err() {
+            return false;
+        }
+    }
+    octets == 4
+}
+
+fn validate_ipv6(s: &str) -> bool {
+    s.parse::<Ipv6Addr>().is_ok()
+}
+
+fn validate_uri(s: &str) -> bool {
+    // an absolute URI must have a scheme
+    Url::parse(s).is_ok()
+}
+
+fn validate_uri_reference(s: &str) -> bool {
+    match Url::parse(s) {
+        Ok(_) => true,
+        // relative references resolve against an arbitrary base
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::options()
+            .base_url(Some(&Url::parse("http://example.com").unwrap()))
+            .parse(s)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn validate_uuid(s: &str) -> bool {
+    let groups = [8, 4, 4, 4, 12];
+    let mut parts = s.split('-');
+    for &len in &groups {
+        match parts.next() {
+            Some(p) if p.len() == len && p.bytes().all(|c| c.is_ascii_hexdigit()) => {}
+            _ => return false,
+        }
+    }
+    parts.next().is_none()
+}
+
+fn validate_regex(s: &str) -> bool {
+    regex::Regex::new(s).is_ok()
+}
+
+fn validate_json_pointer(s: &str) -> bool {
+    if s.is_empty() {
+        return true;
+    }
+    let Some(rest) = s.strip_prefix('/') else {
+        return false;
+    };
+    // within a reference token, '~' must be followed by '0' or '1'
+    let mut bytes = rest.bytes().peekable();
+    while let Some(c) = bytes.next() {
+        if c == b'~' && !matches!(bytes.peek(), Some(b'0') | Some(b'1')) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_builtins() {
+        let formats = Formats::new();
+        let cases = [
+            ("date-time", json!("2018-11-13T20:20:39+00:00"), true),
+            ("date-time", json!("2018-11-13 20:20:39"), false),
+            ("date", json!("2020-02-29"), true), // leap day
+            ("date", json!("2019-02-29"), false),
+            ("time", json!("20:20:39Z"), true),
+            ("time", json!("25:00:00Z"), false),
+            ("duration", json!("P3Y6M4DT12H30M5S"), true),
+            ("duration", json!("P1W"), true),
+            ("duration", json!("P"), false),
+            ("email", json!("a@b.com"), true),
+            ("email", json!("a@@b.com"), false),
+            ("hostname", json!("foo.example.com"), true),
+            ("hostname", json!("-bad.example.com"), false),
+            ("ipv4", json!("192.168.0.1"), true),
+            ("ipv4", json!("256.0.0.1"), false),
+            ("ipv6", json!("::1"), true),
+            ("ipv6", json!("gggg::"), false),
+            ("uri", json!("http://example.com/a"), true),
+            ("uri", json!("/relative"), false),
+            ("uri-reference", json!("/relative"), true),
+            ("uuid", json!("f81d4fae-7dec-11d0-a765-00a0c91e6bf6"), true),
+            ("uuid", json!("not-a-uuid"), false),
+            ("regex", json!("a.*b"), true),
+            ("json-pointer", json!("/a/b~0c"), true),
+            ("json-pointer", json!("a/b"), false),
+            // non-strings are unconstrained
+            ("date-time", json!(42), true),
+        ];
+        for (name, value, want) in cases {
+            assert_eq!(formats.validate(name, &value), want, "{name} {value}");
+        }
+    }
+
+    #[test]
+    fn test_custom_override() {
+        let mut formats = Formats::new();
+        formats.register(
+            "even-digits",
+            Box::new(|v: &Value| match v {
+                Value::String(s) => s.len() % 2 == 0,
+                _ => true,
+            }),
+        );
+        assert!(formats.validate("even-digits", &json!("12")));
+        assert!(!formats.validate("even-digits", &json!("123")));
+
+        // a built-in can be replaced under its own name
+        assert!(!formats.validate("ipv4", &json!("not-an-ip")));
+        formats.register("ipv4", Box::new(|_: &Value| true));
+        assert!(formats.validate("ipv4", &json!("not-an-ip")));
+    }
+}
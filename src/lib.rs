@@ -0,0 +1,15 @@
+//! A JSON Schema validator.
+
+mod draft;
+mod loader;
+mod root;
+mod util;
+
+mod deferred;
+mod formats;
+mod output;
+
+pub use deferred::{CompileMode, UnresolvedRef, UnresolvedReferences};
+pub use formats::{Format, Formats};
+pub use loader::UrlLoader;
+pub use output::{OutputFormat, OutputUnit};
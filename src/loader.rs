@@ -0,0 +1,13 @@
+use serde_json::Value;
+use url::Url;
+
+/// Loads an external schema document identified by its absolute URL.
+///
+/// A loader is registered on a `Compiler` per URL scheme and is called
+/// synchronously whenever compilation needs a document that has not already
+/// been added as a resource. Callers that must fetch documents asynchronously
+/// should instead compile in [`CompileMode::Deferred`](crate::CompileMode),
+/// which avoids the loader entirely.
+pub trait UrlLoader {
+    fn load(&self, url: &Url) -> Result<Value, Box<dyn std::error::Error>>;
+}
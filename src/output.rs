@@ -0,0 +1,181 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// One of the four standardized output structures defined by JSON Schema
+/// 2019-09 and later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Flag,
+    Basic,
+    Detailed,
+    Verbose,
+}
+
+/// A node in a structured validation output tree.
+///
+/// Evaluation builds the `Verbose` shape — one unit per evaluated keyword,
+/// each recording where it sits in the schema and instance — and the other
+/// formats are derived from it with [`OutputUnit::into_format`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputUnit {
+    pub valid: bool,
+    /// JSON pointer into the schema, following `$ref` hops.
+    pub keyword_location: String,
+    /// The same location with `$ref`s resolved to absolute URIs; absent when
+    /// no `$ref` was crossed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_keyword_location: Option<String>,
+    /// JSON pointer into the instance.
+    pub instance_location: String,
+    /// A human-readable message, present on failing units.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// A collected annotation value, present on passing units produced by
+    /// annotating keywords (`title`, `default`, `format`, `properties`, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<Value>,
+    /// Failing child units.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<OutputUnit>,
+    /// Passing child units that carry annotations.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<OutputUnit>,
+}
+
+impl OutputUnit {
+    /// Creates a leaf unit at the given locations.
+    pub fn new(valid: bool, keyword_location: String, instance_location: String) -> Self {
+        OutputUnit {
+            valid,
+            keyword_location,
+            absolute_keyword_location: None,
+            instance_location,
+            error: None,
+            annotation: None,
+            errors: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    // whether this unit carries information worth keeping in a collapsed output.
+    fn is_significant(&self) -> bool {
+        self.error.is_some() || self.annotation.is_some()
+    }
+
+    fn children(&self) -> impl Iterator<Item = &OutputUnit> {
+        self.errors.iter().chain(self.annotations.iter())
+    }
+
+    /// Derives the requested output format from this verbose tree.
+    pub fn into_format(self, format: OutputFormat) -> OutputUnit {
+        match format {
+            OutputFormat::Verbose => self,
+            OutputFormat::Flag => OutputUnit::new(
+                self.valid,
+                self.keyword_location.clone(),
+                self.instance_location.clone(),
+            ),
+            OutputFormat::Basic => self.basic(),
+            OutputFormat::Detailed => self.detailed(),
+        }
+    }
+
+    // flattens the tree into the root plus a single flat list of significant
+    // descendants (errors when invalid, annotations when valid).
+    fn basic(self) -> OutputUnit {
+        let mut flat = Vec::new();
+        for child in self.children() {
+            child.flatten_into(&mut flat);
+        }
+        let mut root = OutputUnit::new(
+            self.valid,
+            self.keyword_location,
+            self.instance_location,
+        );
+        root.absolute_keyword_location = self.absolute_keyword_location;
+        if self.valid {
+            root.annotations = flat;
+        } else {
+            root.errors = flat;
+        }
+        root
+    }
+
+    fn flatten_into(&self, out: &mut Vec<OutputUnit>) {
+        if self.is_significant() {
+            let mut leaf = self.clone();
+            leaf.errors.clear();
+            leaf.annotations.clear();
+            out.push(leaf);
+        }
+        for child in self.children() {
+            child.flatten_into(out);
+        }
+    }
+
+    // collapses chains of failing units: a unit that carries no error or
+    // annotation of its own, has no annotation children and exactly one error
+    // child is replaced by that child. Other branch structure is kept.
+    fn detailed(mut self) -> OutputUnit {
+        let collapse = |units: Vec<OutputUnit>| -> Vec<OutputUnit> {
+            units.into_iter().map(OutputUnit::detailed).collect()
+        };
+        self.errors = collapse(self.errors);
+        self.annotations = collapse(self.annotations);
+        if !self.is_significant()
+            && self.annotations.is_empty()
+            && self.errors.len() == 1
+        {
+            return self.errors.pop().unwrap();
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn verbose() -> OutputUnit {
+        let mut root = OutputUnit::new(false, "".into(), "".into());
+        let mut not = OutputUnit::new(false, "/properties".into(), "".into());
+        let mut leaf = OutputUnit::new(false, "/properties/age/type".into(), "/age".into());
+        leaf.error = Some("want integer, got string".into());
+        not.errors.push(leaf);
+        root.errors.push(not);
+        root
+    }
+
+    #[test]
+    fn test_flag() {
+        let out = verbose().into_format(OutputFormat::Flag);
+        assert!(!out.valid);
+        assert!(out.errors.is_empty());
+    }
+
+    #[test]
+    fn test_basic_flattens() {
+        let out = verbose().into_format(OutputFormat::Basic);
+        assert_eq!(out.errors.len(), 1); // only the significant leaf survives
+        assert_eq!(out.errors[0].keyword_location, "/properties/age/type");
+        assert!(out.errors[0].errors.is_empty());
+    }
+
+    #[test]
+    fn test_detailed_collapses() {
+        let out = verbose().into_format(OutputFormat::Detailed);
+        // the insignificant root/properties chain collapses to the leaf
+        assert_eq!(out.keyword_location, "/properties/age/type");
+    }
+
+    #[test]
+    fn test_verbose_serializes_camel_case() {
+        let mut unit = OutputUnit::new(true, "/title".into(), "".into());
+        unit.annotation = Some(json!("A title"));
+        let v = serde_json::to_value(unit).unwrap();
+        assert_eq!(v["keywordLocation"], "/title");
+        assert_eq!(v["annotation"], "A title");
+    }
+}
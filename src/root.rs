@@ -0,0 +1,16 @@
+use url::Url;
+
+/// A schema resource: a subschema that introduced a new base URI through `$id`
+/// (or draft-4 `id`), keyed in the resource map by its JSON pointer from the
+/// document root.
+#[derive(Debug, Clone)]
+pub(crate) struct Resource {
+    /// The absolute base URI this resource establishes.
+    pub id: Url,
+}
+
+impl Resource {
+    pub(crate) fn new(id: Url) -> Self {
+        Resource { id }
+    }
+}
@@ -0,0 +1,68 @@
+use std::{borrow::Cow, str::Utf8Error};
+
+/// Splits a URI into everything before the first `#` and the fragment after
+/// it (without the `#`). A URI with no fragment yields an empty fragment.
+pub(crate) fn split(url: &str) -> (&str, &str) {
+    match url.split_once('#') {
+        Some((base, fragment)) => (base, fragment),
+        None => (url, ""),
+    }
+}
+
+/// JSON-pointer-escapes a reference token: `~` becomes `~0` and `/` becomes
+/// `~1`.
+pub(crate) fn escape(token: &str) -> Cow<str> {
+    if token.bytes().any(|c| c == b'~' || c == b'/') {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Percent-decodes a URI path, returning the decoded UTF-8 string. Invalid
+/// `%XX` sequences are left untouched; the only error is non-UTF-8 output.
+pub(crate) fn path_unescape(s: &str) -> Result<String, Utf8Error> {
+    let decoded = percent_decode(s);
+    std::str::from_utf8(&decoded).map(|s| s.to_owned())
+}
+
+/// Interprets a URI fragment as a plain-name anchor, percent-decoding it.
+/// Returns `None` for an empty fragment or a JSON pointer (one starting with
+/// `/`), which are not anchors.
+pub(crate) fn fragment_to_anchor(fragment: &str) -> Result<Option<Cow<str>>, Utf8Error> {
+    if fragment.is_empty() || fragment.starts_with('/') {
+        return Ok(None);
+    }
+    let decoded = percent_decode(fragment);
+    let anchor = std::str::from_utf8(&decoded)?.to_owned();
+    Ok(Some(Cow::Owned(anchor)))
+}
+
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) =
+                (hex_val(bytes[i + 1]), hex_val(bytes[i + 2]))
+            {
+                out.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
@@ -41,7 +41,9 @@ fn run_dir(path: &str, draft: Draft) {
         if file_type.is_file() {
             run_file(entry_path, draft);
         } else if file_type.is_dir() {
-            //run_dir(entry_path, draft);
+            // descend into subdirectories (optional/, optional/format/, ...)
+            // now that the format assertion subsystem is available
+            run_dir(entry_path, draft);
         }
     }
 }